@@ -0,0 +1,125 @@
+use tokio::sync::broadcast;
+
+use crate::{errors::Error, models::Ops};
+
+/// Publishes parsed feed messages to any number of independent subscribers.
+///
+/// A logger, a strategy engine, and a UI can each [`subscribe`](Self::subscribe)
+/// and receive every message; a slow subscriber only drops messages for
+/// itself instead of stalling the others or the socket read loop.
+#[derive(Debug, Clone)]
+pub struct FeedBroadcaster {
+    tx: broadcast::Sender<Ops>,
+}
+
+impl FeedBroadcaster {
+    /// Creates a broadcaster that buffers up to `capacity` messages for a
+    /// subscriber that falls behind before it starts dropping the oldest
+    /// ones for that subscriber.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx }
+    }
+
+    /// Publishes `ops` to every current subscriber.
+    ///
+    /// Having no subscribers is not an error; it just means nobody is
+    /// listening right now.
+    pub(crate) fn publish(&self, ops: Ops) {
+        let _ = self.tx.send(ops);
+    }
+
+    /// Subscribes to every published message, optionally filtered to a
+    /// single symbol. System messages always pass the filter, since they
+    /// aren't about any one symbol.
+    #[must_use]
+    pub fn subscribe(&self, symbol: Option<String>) -> Subscription {
+        Subscription {
+            rx: self.tx.subscribe(),
+            symbol,
+        }
+    }
+}
+
+/// A single subscriber's view of a [`FeedBroadcaster`].
+pub struct Subscription {
+    rx: broadcast::Receiver<Ops>,
+    symbol: Option<String>,
+}
+
+impl Subscription {
+    /// Waits for the next message matching this subscription's filter.
+    ///
+    /// Transparently skips messages for other symbols, as well as any that
+    /// were dropped because this subscriber fell too far behind.
+    ///
+    /// # Errors
+    /// Returns an error once the broadcaster has been dropped and no more
+    /// messages will ever arrive.
+    pub async fn recv(&mut self) -> Result<Ops, Error> {
+        loop {
+            match self.rx.recv().await {
+                Ok(ops) if self.matches(&ops) => return Ok(ops),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Err(Error::BroadcasterClosed),
+            }
+        }
+    }
+
+    fn matches(&self, ops: &Ops) -> bool {
+        match (&self.symbol, ops.symbol()) {
+            (Some(wanted), Some(symbol)) => wanted == symbol,
+            (Some(_), None) | (None, _) => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::models::{SystemMessage, Trade};
+
+    fn trade(symbol: &str) -> Ops {
+        Ops::Trade(Trade {
+            symbol: symbol.to_string(),
+            last: 1.0,
+            last_size: 1,
+            timestamp: datetime!(2023-01-01 0:00:00),
+        })
+    }
+
+    #[tokio::test]
+    async fn subscription_filters_to_its_symbol() {
+        let broadcaster = FeedBroadcaster::new(16);
+        let mut sub = broadcaster.subscribe(Some("AAPL".to_string()));
+
+        broadcaster.publish(trade("MSFT"));
+        broadcaster.publish(trade("AAPL"));
+
+        assert_eq!(sub.recv().await.unwrap(), trade("AAPL"));
+    }
+
+    #[tokio::test]
+    async fn subscription_without_a_filter_receives_everything() {
+        let broadcaster = FeedBroadcaster::new(16);
+        let mut sub = broadcaster.subscribe(None);
+
+        broadcaster.publish(trade("MSFT"));
+        assert_eq!(sub.recv().await.unwrap(), trade("MSFT"));
+    }
+
+    #[tokio::test]
+    async fn system_messages_always_pass_a_symbol_filter() {
+        let broadcaster = FeedBroadcaster::new(16);
+        let mut sub = broadcaster.subscribe(Some("AAPL".to_string()));
+        let system = Ops::System(SystemMessage { message: "hi".to_string() });
+
+        broadcaster.publish(system.clone());
+
+        assert_eq!(sub.recv().await.unwrap(), system);
+    }
+}
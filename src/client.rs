@@ -1,70 +1,194 @@
-use std::cmp::min;
+use std::path::{Path, PathBuf};
 
-use async_channel::Sender;
-use memmem::{Searcher, TwoWaySearcher};
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
+    fs::File,
+    io::AsyncWriteExt,
     net::TcpStream,
+    sync::{broadcast, mpsc},
 };
+use tokio_stream::StreamExt;
+use tokio_util::codec::Framed;
 
-use crate::errors::ClientError;
+use crate::{
+    broadcast::FeedBroadcaster,
+    command::IQFeedHandle,
+    config::Config,
+    errors::Error,
+    framing::LineDecoder,
+    models::Ops,
+    supervisor::{self, ConnectionState, Service},
+};
 
 pub struct IQFeed {
-    stream: TcpStream,
-    ice_breaker: TwoWaySearcher<'static>,
-    tx: Sender<Vec<u8>>,
-    buffer: Vec<u8>,
+    stream: Framed<TcpStream, LineDecoder>,
+    broadcaster: FeedBroadcaster,
+    commands: mpsc::Receiver<crate::command::Command>,
+    recording: Option<File>,
 }
 
 impl IQFeed {
-    /// Created a new IQFeed Client connection
+    /// Opens a connection to the Level 1 (streaming quote) port.
+    ///
+    /// # Errors
+    pub async fn connect_level1(cfg: &Config, broadcaster: FeedBroadcaster) -> Result<(Self, IQFeedHandle), Error> {
+        Self::connect(&cfg.host, cfg.level1_port, broadcaster).await
+    }
+
+    /// Opens a connection to the historical/lookup port.
+    ///
+    /// # Errors
+    pub async fn connect_lookup(cfg: &Config, broadcaster: FeedBroadcaster) -> Result<(Self, IQFeedHandle), Error> {
+        Self::connect(&cfg.host, cfg.lookup_port, broadcaster).await
+    }
+
+    /// Opens a connection to the admin port.
+    ///
+    /// # Errors
+    pub async fn connect_admin(cfg: &Config, broadcaster: FeedBroadcaster) -> Result<(Self, IQFeedHandle), Error> {
+        Self::connect(&cfg.host, cfg.admin_port, broadcaster).await
+    }
+
+    /// Opens a connection to the derivatives port.
+    ///
+    /// # Errors
+    pub async fn connect_derivative(cfg: &Config, broadcaster: FeedBroadcaster) -> Result<(Self, IQFeedHandle), Error> {
+        Self::connect(&cfg.host, cfg.derivative_port, broadcaster).await
+    }
+
+    /// Opens a `TcpStream` to `host:port`, performs the IQConnect protocol
+    /// handshake, and returns the feed alongside a cloneable handle for
+    /// issuing subscription commands once `process` is running.
     ///
     /// # Errors
-    pub async fn new(tx: Sender<Vec<u8>>) -> Result<Self, ClientError> {
-        let mut stream = TcpStream::connect("").await?;
+    async fn connect(host: &str, port: u16, broadcaster: FeedBroadcaster) -> Result<(Self, IQFeedHandle), Error> {
+        let mut stream = TcpStream::connect((host, port)).await?;
         stream.write_all(b"S,SET PROTOCOL,6.2\n").await?;
-        Ok(Self {
-            stream,
-            ice_breaker: TwoWaySearcher::new(b"\n"),
-            tx,
-            buffer: Vec::new(),
-        })
+        let (command_tx, command_rx) = mpsc::channel(32);
+
+        let feed = Self {
+            stream: Framed::new(stream, LineDecoder::default()),
+            broadcaster,
+            commands: command_rx,
+            recording: None,
+        };
+        Ok((feed, IQFeedHandle { commands: command_tx }))
     }
 
-    /// Sends a request to watch a symbol
+    /// Tees every line `process` reads off the socket to `path` as it
+    /// forwards it, newline-preserved, so the session can later be played
+    /// back with [`crate::replay::ReplayFeed`] without a live IQConnect
+    /// connection.
+    ///
+    /// Opens `path` for appending rather than truncating it, so a feed
+    /// that gets re-pointed at the same path after a reconnect (see
+    /// [`IQFeed::supervise_level1`]) extends the capture instead of
+    /// stomping on what was already recorded.
     ///
     /// # Errors
-    /// This will only error if there's an issue with the `TCPStream`. Any
-    /// errors with watching the symbol will occur when `process` is called.
-    pub async fn watch_trades(mut self, symbol: &str) -> Result<(), ClientError> {
-        let command = format!("w{}\n", symbol.to_uppercase());
-        Ok(self.stream.write_all(command.as_bytes()).await?)
+    /// Returns an error if `path` can't be created or opened.
+    pub async fn record_to(&mut self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.recording = Some(File::options().append(true).create(true).open(path).await?);
+        Ok(())
+    }
+
+    /// Opens a supervised connection to the Level 1 port.
+    ///
+    /// Unlike [`IQFeed::connect_level1`], the returned handle stays usable
+    /// across disconnects: the supervisor retries with backoff, redoes the
+    /// handshake, and replays every active watch command, spawning its own
+    /// task to drive `process` on each underlying connection. Connection
+    /// lifecycle transitions are published on the returned receiver.
+    ///
+    /// If `record_to` is `Some`, every reconnect calls [`IQFeed::record_to`]
+    /// on the fresh connection before it starts processing, so capture-to-
+    /// file recording keeps running across the supervisor's reconnects
+    /// instead of being limited to a single, unsupervised connection.
+    #[must_use]
+    pub fn supervise_level1(
+        cfg: Config,
+        broadcaster: FeedBroadcaster,
+        record_to: Option<PathBuf>,
+    ) -> (IQFeedHandle, broadcast::Receiver<ConnectionState>) {
+        supervisor::spawn(cfg, broadcaster, Service::Level1, record_to)
+    }
+
+    /// Opens a supervised connection to the historical/lookup port. See
+    /// [`IQFeed::supervise_level1`] for the reconnect and recording
+    /// behavior.
+    #[must_use]
+    pub fn supervise_lookup(
+        cfg: Config,
+        broadcaster: FeedBroadcaster,
+        record_to: Option<PathBuf>,
+    ) -> (IQFeedHandle, broadcast::Receiver<ConnectionState>) {
+        supervisor::spawn(cfg, broadcaster, Service::Lookup, record_to)
+    }
+
+    /// Opens a supervised connection to the admin port. See
+    /// [`IQFeed::supervise_level1`] for the reconnect and recording
+    /// behavior.
+    #[must_use]
+    pub fn supervise_admin(
+        cfg: Config,
+        broadcaster: FeedBroadcaster,
+        record_to: Option<PathBuf>,
+    ) -> (IQFeedHandle, broadcast::Receiver<ConnectionState>) {
+        supervisor::spawn(cfg, broadcaster, Service::Admin, record_to)
+    }
+
+    /// Opens a supervised connection to the derivatives port. See
+    /// [`IQFeed::supervise_level1`] for the reconnect and recording
+    /// behavior.
+    #[must_use]
+    pub fn supervise_derivative(
+        cfg: Config,
+        broadcaster: FeedBroadcaster,
+        record_to: Option<PathBuf>,
+    ) -> (IQFeedHandle, broadcast::Receiver<ConnectionState>) {
+        supervisor::spawn(cfg, broadcaster, Service::Derivative, record_to)
     }
 
     /// Starts processing of the `TCPStream`. This should be sent to a tokio
     /// task.
     ///
+    /// Owns the socket for the lifetime of the connection, `select!`-ing
+    /// between incoming TCP data and commands sent over the
+    /// [`IQFeedHandle`] returned alongside this feed, so one connection can
+    /// manage an evolving set of subscriptions. Framing is handled by
+    /// [`LineDecoder`], which only looks at newly read bytes and retains
+    /// any partial tail between reads, so this loop never rescans data it
+    /// has already seen. Every complete line is classified by its leading
+    /// message-type token, parsed into an [`Ops`], and published to every
+    /// subscriber of the feed's [`FeedBroadcaster`], so consumers never see
+    /// raw protocol bytes. If [`IQFeed::record_to`] was called, the raw
+    /// line is also appended to that file before parsing. A line that
+    /// fails to parse is skipped rather than ending the connection.
+    ///
     /// # Errors
-    /// This will return an error if the Sender channel is closed.
-    pub async fn process(mut self) -> Result<(), ClientError> {
-        let mut buf = vec![0; 2048];
-        let mut scan_read = 0;
-
+    /// This will return an error if reading the socket fails or a write (to
+    /// the stream or to a recording file) fails. If the peer closes the
+    /// connection cleanly, this returns `Ok(())`.
+    pub async fn process(mut self) -> Result<(), Error> {
         loop {
-            let r = self.stream.read(&mut buf).await?;
-            self.buffer.extend_from_slice(&buf[0..r]);
-
-            loop {
-                if let Some(e) = self.ice_breaker.search_in(&self.buffer[scan_read..]) {
-                    if e == 0 {
-                        self.buffer.drain(0..1);
-                        continue;
-                    };
-
-                    self.tx.send(self.buffer.drain(0..(scan_read + e)).collect()).await?;
-                } else {
-                    scan_read = min(self.buffer.len() - 1, 0);
-                    break;
+            tokio::select! {
+                line = self.stream.next() => {
+                    let Some(line) = line else { return Ok(()) };
+                    let line = line?;
+
+                    if let Some(recording) = &mut self.recording {
+                        recording.write_all(&line).await?;
+                        recording.write_all(b"\n").await?;
+                    }
+
+                    // A single line failing to parse isn't worth tearing
+                    // down the whole connection over; skip it and keep
+                    // reading.
+                    if let Ok(ops) = Ops::parse(&line) {
+                        self.broadcaster.publish(ops);
+                    }
+                }
+                Some(command) = self.commands.recv() => {
+                    self.stream.get_mut().write_all(command.encode().as_bytes()).await?;
                 }
             }
         }
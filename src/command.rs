@@ -0,0 +1,162 @@
+use tokio::sync::mpsc;
+
+use crate::errors::Error;
+
+/// A subscription command accepted by a running [`crate::IQFeed::process`]
+/// loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Watch Level 1 updates for a symbol.
+    Watch(String),
+    /// Stop watching a symbol.
+    Unwatch(String),
+    /// Watch trade prints only for a symbol.
+    WatchTrades(String),
+    /// Stop watching trade prints for a symbol. Sent as the same `r`
+    /// command as [`Command::Unwatch`], so it cancels every watch on the
+    /// symbol.
+    UnwatchTrades(String),
+    /// Watch Level 2 (regional) updates for a symbol.
+    WatchLevel2(String),
+    /// Stop watching Level 2 updates for a symbol. Sent as the same `r`
+    /// command as [`Command::Unwatch`], so it cancels every watch on the
+    /// symbol.
+    UnwatchLevel2(String),
+}
+
+impl Command {
+    /// Encodes this command as the wire command IQConnect expects.
+    pub(crate) fn encode(&self) -> String {
+        match self {
+            Command::Watch(symbol) => format!("w{}\n", symbol.to_uppercase()),
+            Command::WatchLevel2(symbol) => format!("l{}\n", symbol.to_uppercase()),
+            Command::Unwatch(symbol) | Command::UnwatchLevel2(symbol) | Command::UnwatchTrades(symbol) => {
+                format!("r{}\n", symbol.to_uppercase())
+            }
+            Command::WatchTrades(symbol) => format!("t{}\n", symbol.to_uppercase()),
+        }
+    }
+
+    /// Whether this command cancels a previous watch rather than starting
+    /// one.
+    pub(crate) fn is_unwatch(&self) -> bool {
+        matches!(self, Command::Unwatch(_) | Command::UnwatchTrades(_) | Command::UnwatchLevel2(_))
+    }
+
+    /// A (kind, symbol) key identifying which active subscription this
+    /// command affects, so a supervisor can track the current
+    /// subscription set across reconnects and replace or cancel entries.
+    pub(crate) fn active_key(&self) -> (&'static str, String) {
+        match self {
+            Command::Watch(symbol) | Command::Unwatch(symbol) => ("watch", symbol.clone()),
+            Command::WatchTrades(symbol) | Command::UnwatchTrades(symbol) => ("trades", symbol.clone()),
+            Command::WatchLevel2(symbol) | Command::UnwatchLevel2(symbol) => ("level2", symbol.clone()),
+        }
+    }
+}
+
+/// A cloneable handle for sending subscription commands to a running
+/// [`crate::IQFeed::process`] loop.
+#[derive(Debug, Clone)]
+pub struct IQFeedHandle {
+    pub(crate) commands: mpsc::Sender<Command>,
+}
+
+impl IQFeedHandle {
+    /// Subscribes to Level 1 updates for `symbol`.
+    ///
+    /// # Errors
+    /// Returns an error if the `process` loop has stopped.
+    pub async fn watch(&self, symbol: &str) -> Result<(), Error> {
+        self.send(Command::Watch(symbol.to_string())).await
+    }
+
+    /// Unsubscribes from `symbol`.
+    ///
+    /// # Errors
+    /// Returns an error if the `process` loop has stopped.
+    pub async fn unwatch(&self, symbol: &str) -> Result<(), Error> {
+        self.send(Command::Unwatch(symbol.to_string())).await
+    }
+
+    /// Subscribes to trade prints only for `symbol`.
+    ///
+    /// # Errors
+    /// Returns an error if the `process` loop has stopped.
+    pub async fn watch_trades(&self, symbol: &str) -> Result<(), Error> {
+        self.send(Command::WatchTrades(symbol.to_string())).await
+    }
+
+    /// Unsubscribes from trade prints for `symbol`. This sends the same
+    /// command as [`IQFeedHandle::unwatch`], so every watch on `symbol` is
+    /// cancelled.
+    ///
+    /// # Errors
+    /// Returns an error if the `process` loop has stopped.
+    pub async fn unwatch_trades(&self, symbol: &str) -> Result<(), Error> {
+        self.send(Command::UnwatchTrades(symbol.to_string())).await
+    }
+
+    /// Subscribes to Level 2 updates for `symbol`.
+    ///
+    /// # Errors
+    /// Returns an error if the `process` loop has stopped.
+    pub async fn watch_level2(&self, symbol: &str) -> Result<(), Error> {
+        self.send(Command::WatchLevel2(symbol.to_string())).await
+    }
+
+    /// Unsubscribes from Level 2 updates for `symbol`. This sends the same
+    /// command as [`IQFeedHandle::unwatch`], so every watch on `symbol` is
+    /// cancelled.
+    ///
+    /// # Errors
+    /// Returns an error if the `process` loop has stopped.
+    pub async fn unwatch_level2(&self, symbol: &str) -> Result<(), Error> {
+        self.send(Command::UnwatchLevel2(symbol.to_string())).await
+    }
+
+    pub(crate) async fn send(&self, command: Command) -> Result<(), Error> {
+        self.commands.send(command).await.map_err(|_| Error::CommandChannelClosed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_and_watch_level2_use_distinct_wire_commands() {
+        assert_ne!(
+            Command::Watch("AAPL".to_string()).encode(),
+            Command::WatchLevel2("AAPL".to_string()).encode()
+        );
+    }
+
+    #[test]
+    fn encodes_expected_wire_commands() {
+        assert_eq!(Command::Watch("aapl".to_string()).encode(), "wAAPL\n");
+        assert_eq!(Command::Unwatch("aapl".to_string()).encode(), "rAAPL\n");
+        assert_eq!(Command::WatchTrades("aapl".to_string()).encode(), "tAAPL\n");
+        assert_eq!(Command::UnwatchTrades("aapl".to_string()).encode(), "rAAPL\n");
+        assert_eq!(Command::WatchLevel2("aapl".to_string()).encode(), "lAAPL\n");
+        assert_eq!(Command::UnwatchLevel2("aapl".to_string()).encode(), "rAAPL\n");
+    }
+
+    #[test]
+    fn is_unwatch_classifies_variants() {
+        assert!(Command::Unwatch("AAPL".to_string()).is_unwatch());
+        assert!(Command::UnwatchTrades("AAPL".to_string()).is_unwatch());
+        assert!(Command::UnwatchLevel2("AAPL".to_string()).is_unwatch());
+        assert!(!Command::Watch("AAPL".to_string()).is_unwatch());
+        assert!(!Command::WatchTrades("AAPL".to_string()).is_unwatch());
+        assert!(!Command::WatchLevel2("AAPL".to_string()).is_unwatch());
+    }
+
+    #[test]
+    fn active_key_groups_watch_and_unwatch_of_the_same_kind_together() {
+        assert_eq!(Command::Watch("AAPL".to_string()).active_key(), ("watch", "AAPL".to_string()));
+        assert_eq!(Command::Unwatch("AAPL".to_string()).active_key(), ("watch", "AAPL".to_string()));
+        assert_eq!(Command::WatchTrades("AAPL".to_string()).active_key(), ("trades", "AAPL".to_string()));
+        assert_eq!(Command::WatchLevel2("AAPL".to_string()).active_key(), ("level2", "AAPL".to_string()));
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+use crate::errors::Error;
+
+/// Connection settings for an IQConnect instance.
+///
+/// Deserializable from TOML, e.g.:
+///
+/// ```toml
+/// host = "127.0.0.1"
+/// level1_port = 5009
+/// lookup_port = 9100
+/// admin_port = 9300
+/// derivative_port = 9400
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub host: String,
+    pub level1_port: u16,
+    pub lookup_port: u16,
+    pub admin_port: u16,
+    pub derivative_port: u16,
+}
+
+impl Config {
+    /// Parses a `Config` from a TOML document.
+    ///
+    /// # Errors
+    /// Returns an error if `raw` is not a valid TOML document or is missing
+    /// a required field.
+    pub fn from_toml(raw: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(raw)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_complete_config() {
+        let cfg = Config::from_toml(
+            r#"
+            host = "127.0.0.1"
+            level1_port = 5009
+            lookup_port = 9100
+            admin_port = 9300
+            derivative_port = 9400
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(cfg.host, "127.0.0.1");
+        assert_eq!(cfg.level1_port, 5009);
+        assert_eq!(cfg.lookup_port, 9100);
+        assert_eq!(cfg.admin_port, 9300);
+        assert_eq!(cfg.derivative_port, 9400);
+    }
+
+    #[test]
+    fn missing_field_is_an_error() {
+        assert!(Config::from_toml(r#"host = "127.0.0.1""#).is_err());
+    }
+
+    #[test]
+    fn invalid_toml_is_an_error() {
+        assert!(Config::from_toml("not valid toml = = =").is_err());
+    }
+}
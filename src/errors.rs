@@ -1,8 +1,6 @@
 use thiserror::Error;
 use tokio::io;
 
-use crate::models::Ops;
-
 /// `ParsingError` is an error returned from anything having to do with parsing
 /// data.
 #[derive(Error, Debug)]
@@ -15,6 +13,24 @@ pub enum Error {
     Float(#[from] fast_float::Error),
     #[error("error parsing number")]
     Tcp(#[from] io::Error),
-    #[error("error sending msg over channel")]
-    Channel(#[from] async_channel::SendError<Ops>),
+    #[error("broadcaster closed")]
+    BroadcasterClosed,
+    #[error("malformed message")]
+    MalformedMessage,
+    #[error("error parsing config")]
+    Config(#[from] toml::de::Error),
+    #[error("command channel closed")]
+    CommandChannelClosed,
+    #[error("replay speed scale must be a positive, finite number")]
+    InvalidReplaySpeed,
+}
+
+impl Error {
+    /// Whether this error means the underlying connection itself is gone,
+    /// as opposed to a single line failing to parse. Lets a supervisor
+    /// tell a genuine outage from transient protocol noise.
+    #[must_use]
+    pub fn is_connection_fatal(&self) -> bool {
+        matches!(self, Error::Tcp(_))
+    }
 }
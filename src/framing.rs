@@ -0,0 +1,96 @@
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::Decoder;
+
+use crate::errors::Error;
+
+/// Frames a raw IQFeed byte stream into newline-delimited lines.
+///
+/// Used as a `tokio_util::codec::Decoder` behind `Framed` so `process`
+/// only ever sees newly read bytes plus whatever partial tail is still
+/// buffered, instead of rescanning everything accumulated so far on every
+/// read: `next_index` remembers how far a previous call already scanned
+/// without finding a newline, the same way `tokio_util::codec::LinesCodec`
+/// does, so a later call only looks at bytes appended since. Empty
+/// leading lines (IQConnect occasionally sends a bare `\n`) are skipped
+/// rather than yielded.
+#[derive(Debug, Default)]
+pub(crate) struct LineDecoder {
+    next_index: usize,
+}
+
+impl Decoder for LineDecoder {
+    type Item = Vec<u8>;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            match src[self.next_index..].iter().position(|&byte| byte == b'\n') {
+                Some(offset) => {
+                    let pos = self.next_index + offset;
+                    if pos == 0 {
+                        src.advance(1);
+                        self.next_index = 0;
+                        continue;
+                    }
+                    let line = src.split_to(pos).to_vec();
+                    src.advance(1);
+                    self.next_index = 0;
+                    return Ok(Some(line));
+                }
+                None => {
+                    self.next_index = src.len();
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_across_fragmented_reads() {
+        let mut decoder = LineDecoder::default();
+        let mut buf = BytesMut::new();
+
+        buf.extend_from_slice(b"AAPL");
+        assert_eq!(decoder.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(b",150.25");
+        assert_eq!(decoder.decode(&mut buf).unwrap(), None);
+
+        buf.extend_from_slice(b"\n");
+        assert_eq!(decoder.decode(&mut buf).unwrap(), Some(b"AAPL,150.25".to_vec()));
+    }
+
+    #[test]
+    fn skips_empty_leading_lines() {
+        let mut decoder = LineDecoder::default();
+        let mut buf = BytesMut::from(&b"\n\nQ,AAPL\n"[..]);
+        assert_eq!(decoder.decode(&mut buf).unwrap(), Some(b"Q,AAPL".to_vec()));
+    }
+
+    /// Regression test: a prior version of this decoder re-scanned the
+    /// whole buffer from index 0 on every call, so a byte-at-a-time feed
+    /// touched the buffer a quadratic number of times. This asserts
+    /// `next_index` actually advances past bytes already confirmed
+    /// newline-free instead of re-examining them.
+    #[test]
+    fn does_not_rescan_bytes_already_confirmed_newline_free() {
+        let mut decoder = LineDecoder::default();
+        let mut buf = BytesMut::new();
+
+        for _ in 0..2000 {
+            buf.extend_from_slice(b"x");
+            assert_eq!(decoder.decode(&mut buf).unwrap(), None);
+        }
+        assert_eq!(decoder.next_index, 2000);
+
+        buf.extend_from_slice(b"\n");
+        let line = decoder.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(line.len(), 2000);
+        assert_eq!(decoder.next_index, 0);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod broadcast;
+pub mod client;
+pub mod command;
+pub mod config;
+pub mod errors;
+mod framing;
+pub mod models;
+pub mod replay;
+pub mod supervisor;
+
+pub use broadcast::{FeedBroadcaster, Subscription};
+pub use client::IQFeed;
+pub use command::{Command, IQFeedHandle};
+pub use config::Config;
+pub use errors::Error;
+pub use replay::{ReplayFeed, ReplaySpeed};
+pub use supervisor::ConnectionState;
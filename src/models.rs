@@ -0,0 +1,233 @@
+use time::{macros::format_description, PrimitiveDateTime};
+
+use crate::errors::Error;
+
+const TIMESTAMP_FORMAT: &[time::format_description::FormatItem<'_>] =
+    format_description!("[year][month][day] [hour]:[minute]:[second]");
+
+/// A single parsed message coming off an IQFeed socket.
+///
+/// `process` classifies every line by its leading message-type token before
+/// it ever reaches a consumer, so callers work with these variants instead
+/// of re-parsing raw bytes themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ops {
+    /// `Q`/`P` update message for a watched symbol.
+    Update(Update),
+    /// `T` trade message for a watched symbol.
+    Trade(Trade),
+    /// `S` system message (connection/session notifications).
+    System(SystemMessage),
+    /// `F` fundamental message for a watched symbol.
+    Fundamental(Fundamental),
+    /// `n`/`N` protocol error reported by IQConnect itself, e.g. "symbol
+    /// not found". Expected traffic, not a parse failure.
+    ProtocolError(String),
+    /// A message whose leading type token isn't one this crate classifies
+    /// yet. Kept around instead of discarded so callers can still inspect
+    /// the raw line.
+    Unknown(String),
+}
+
+impl Ops {
+    /// The symbol this message is about, if any.
+    ///
+    /// System messages aren't about a particular symbol and return `None`.
+    #[must_use]
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            Ops::Update(update) => Some(&update.symbol),
+            Ops::Trade(trade) => Some(&trade.symbol),
+            Ops::System(_) => None,
+            Ops::Fundamental(fundamental) => Some(&fundamental.symbol),
+            Ops::ProtocolError(_) | Ops::Unknown(_) => None,
+        }
+    }
+
+    /// The timestamp carried by this message, if any.
+    ///
+    /// System and fundamental messages don't carry one and return `None`.
+    #[must_use]
+    pub fn timestamp(&self) -> Option<PrimitiveDateTime> {
+        match self {
+            Ops::Update(update) => Some(update.timestamp),
+            Ops::Trade(trade) => Some(trade.timestamp),
+            Ops::System(_) | Ops::Fundamental(_) | Ops::ProtocolError(_) | Ops::Unknown(_) => None,
+        }
+    }
+}
+
+/// A quote update: latest trade plus the current inside market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Update {
+    pub symbol: String,
+    pub last: f64,
+    pub bid: f64,
+    pub ask: f64,
+    pub last_size: u64,
+    pub timestamp: PrimitiveDateTime,
+}
+
+/// A trade print for a watched symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub symbol: String,
+    pub last: f64,
+    pub last_size: u64,
+    pub timestamp: PrimitiveDateTime,
+}
+
+/// A system message, e.g. session or connection notifications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemMessage {
+    pub message: String,
+}
+
+/// A fundamental data message for a watched symbol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fundamental {
+    pub symbol: String,
+    pub fields: Vec<String>,
+}
+
+impl Ops {
+    /// Classifies and parses a single line from the feed.
+    ///
+    /// The leading token before the first comma selects the parser; the
+    /// remaining comma-separated fields are handed to it. An unrecognized
+    /// leading token or an IQConnect `n`/`N` protocol-error line is
+    /// expected traffic, not a parse failure, so both come back as `Ok`
+    /// values ([`Ops::Unknown`] / [`Ops::ProtocolError`]) rather than
+    /// tearing down whatever loop is reading the feed.
+    ///
+    /// # Errors
+    /// Returns an error if the line isn't valid UTF-8, a required field is
+    /// missing, or a field fails to parse as a number, float, or
+    /// timestamp.
+    pub fn parse(line: &[u8]) -> Result<Self, Error> {
+        let line = std::str::from_utf8(line).map_err(|_| Error::MalformedMessage)?;
+        let (msg_type, rest) = line.split_once(',').ok_or(Error::MalformedMessage)?;
+        let mut fields = rest.split(',');
+
+        match msg_type {
+            "Q" | "P" => Ok(Ops::Update(parse_update(&mut fields)?)),
+            "T" => Ok(Ops::Trade(parse_trade(&mut fields)?)),
+            "S" => Ok(Ops::System(SystemMessage {
+                message: rest.to_string(),
+            })),
+            "F" => Ok(Ops::Fundamental(parse_fundamental(&mut fields)?)),
+            "n" | "N" => Ok(Ops::ProtocolError(rest.to_string())),
+            _ => Ok(Ops::Unknown(line.to_string())),
+        }
+    }
+}
+
+fn next_field<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Result<&'a str, Error> {
+    fields.next().ok_or(Error::MalformedMessage)
+}
+
+fn parse_timestamp<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Result<PrimitiveDateTime, Error> {
+    Ok(PrimitiveDateTime::parse(next_field(fields)?, TIMESTAMP_FORMAT)?)
+}
+
+fn parse_update<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Result<Update, Error> {
+    let symbol = next_field(fields)?.to_string();
+    let last = fast_float::parse(next_field(fields)?)?;
+    let bid = fast_float::parse(next_field(fields)?)?;
+    let ask = fast_float::parse(next_field(fields)?)?;
+    let last_size = lexical::parse(next_field(fields)?)?;
+    let timestamp = parse_timestamp(fields)?;
+
+    Ok(Update {
+        symbol,
+        last,
+        bid,
+        ask,
+        last_size,
+        timestamp,
+    })
+}
+
+fn parse_trade<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Result<Trade, Error> {
+    let symbol = next_field(fields)?.to_string();
+    let last = fast_float::parse(next_field(fields)?)?;
+    let last_size = lexical::parse(next_field(fields)?)?;
+    let timestamp = parse_timestamp(fields)?;
+
+    Ok(Trade {
+        symbol,
+        last,
+        last_size,
+        timestamp,
+    })
+}
+
+fn parse_fundamental<'a>(fields: &mut impl Iterator<Item = &'a str>) -> Result<Fundamental, Error> {
+    let symbol = next_field(fields)?.to_string();
+    let fields = fields.map(str::to_string).collect();
+
+    Ok(Fundamental { symbol, fields })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_update() {
+        let ops = Ops::parse(b"Q,AAPL,150.25,150.20,150.30,100,20230101 09:30:00").unwrap();
+        assert_eq!(
+            ops,
+            Ops::Update(Update {
+                symbol: "AAPL".to_string(),
+                last: 150.25,
+                bid: 150.20,
+                ask: 150.30,
+                last_size: 100,
+                timestamp: PrimitiveDateTime::parse("20230101 09:30:00", TIMESTAMP_FORMAT).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_trade() {
+        let ops = Ops::parse(b"T,AAPL,150.25,100,20230101 09:30:00").unwrap();
+        assert_eq!(
+            ops,
+            Ops::Trade(Trade {
+                symbol: "AAPL".to_string(),
+                last: 150.25,
+                last_size: 100,
+                timestamp: PrimitiveDateTime::parse("20230101 09:30:00", TIMESTAMP_FORMAT).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_system_message() {
+        let ops = Ops::parse(b"S,SERVER DISCONNECTED").unwrap();
+        assert_eq!(ops, Ops::System(SystemMessage { message: "SERVER DISCONNECTED".to_string() }));
+    }
+
+    #[test]
+    fn parses_protocol_error_without_failing() {
+        let ops = Ops::parse(b"n,AAPL").unwrap();
+        assert_eq!(ops, Ops::ProtocolError("AAPL".to_string()));
+    }
+
+    #[test]
+    fn parses_unknown_message_type_without_failing() {
+        let ops = Ops::parse(b"Z,whatever,this,is").unwrap();
+        assert_eq!(ops, Ops::Unknown("Z,whatever,this,is".to_string()));
+    }
+
+    #[test]
+    fn missing_field_is_an_error() {
+        assert!(Ops::parse(b"Q,AAPL").is_err());
+    }
+
+    #[test]
+    fn bad_number_is_an_error() {
+        assert!(Ops::parse(b"T,AAPL,not-a-float,100,20230101 09:30:00").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::{path::Path, time::Duration as StdDuration};
+
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader},
+};
+
+use crate::{broadcast::FeedBroadcaster, errors::Error, models::Ops};
+
+/// How quickly a [`ReplayFeed`] plays back a captured session.
+#[derive(Debug, Clone, Copy)]
+pub enum ReplaySpeed {
+    /// Emit every message as soon as it's read, ignoring its original
+    /// timing.
+    AsFastAsPossible,
+    /// Sleep between messages to reproduce their original pacing from the
+    /// embedded timestamps, scaled by this factor (`1.0` is real time).
+    RealTime(f64),
+}
+
+/// Replays a file captured by [`crate::IQFeed::record_to`] into the same
+/// parsing/broadcast pipeline a live connection uses, so a captured
+/// session can be replayed without IQConnect running.
+pub struct ReplayFeed {
+    reader: BufReader<File>,
+    broadcaster: FeedBroadcaster,
+    speed: ReplaySpeed,
+}
+
+impl ReplayFeed {
+    /// Opens a capture file written by [`crate::IQFeed::record_to`] for
+    /// replay.
+    ///
+    /// # Errors
+    /// Returns an error if `path` can't be opened, or if `speed` is a
+    /// [`ReplaySpeed::RealTime`] with a non-positive or non-finite scale
+    /// (it divides the elapsed time between messages, so it can't be zero,
+    /// negative, or NaN).
+    pub async fn open(path: impl AsRef<Path>, broadcaster: FeedBroadcaster, speed: ReplaySpeed) -> Result<Self, Error> {
+        if let ReplaySpeed::RealTime(scale) = speed {
+            if !scale.is_finite() || scale <= 0.0 {
+                return Err(Error::InvalidReplaySpeed);
+            }
+        }
+
+        let file = File::open(path).await?;
+        Ok(Self {
+            reader: BufReader::new(file),
+            broadcaster,
+            speed,
+        })
+    }
+
+    /// Replays the whole capture, publishing each parsed message to the
+    /// broadcaster exactly as a live [`crate::IQFeed::process`] would. A
+    /// line that fails to parse is skipped rather than ending the replay,
+    /// mirroring how `process` handles the same situation on a live
+    /// connection.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read.
+    pub async fn process(mut self) -> Result<(), Error> {
+        let mut line = Vec::new();
+        let mut previous_timestamp = None;
+
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line).await? == 0 {
+                return Ok(());
+            }
+
+            let trimmed = line.strip_suffix(b"\n").unwrap_or(&line[..]);
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let Ok(ops) = Ops::parse(trimmed) else {
+                continue;
+            };
+            self.pace(&ops, &mut previous_timestamp).await;
+            self.broadcaster.publish(ops);
+        }
+    }
+
+    async fn pace(&self, ops: &Ops, previous_timestamp: &mut Option<time::PrimitiveDateTime>) {
+        if let ReplaySpeed::RealTime(scale) = self.speed {
+            if let (Some(previous), Some(current)) = (*previous_timestamp, ops.timestamp()) {
+                let elapsed = current - previous;
+                if elapsed.is_positive() {
+                    tokio::time::sleep(StdDuration::from_secs_f64(elapsed.as_seconds_f64() / scale)).await;
+                }
+            }
+        }
+
+        if let Some(timestamp) = ops.timestamp() {
+            *previous_timestamp = Some(timestamp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_non_positive_or_non_finite_real_time_scale() {
+        let broadcaster = FeedBroadcaster::new(16);
+
+        for scale in [0.0, -1.0, f64::NAN] {
+            let result = ReplayFeed::open("/nonexistent/path", broadcaster.clone(), ReplaySpeed::RealTime(scale)).await;
+            assert!(matches!(result, Err(Error::InvalidReplaySpeed)));
+        }
+    }
+
+    #[tokio::test]
+    async fn skips_a_line_that_fails_to_parse_instead_of_ending_the_replay() {
+        let path = std::env::temp_dir().join("iqfeed_rs_replay_test_skips_bad_line.txt");
+        tokio::fs::write(&path, b"not a real line\nT,AAPL,150.25,100,20230101 09:30:00\n")
+            .await
+            .unwrap();
+
+        let broadcaster = FeedBroadcaster::new(16);
+        let mut sub = broadcaster.subscribe(None);
+        let feed = ReplayFeed::open(&path, broadcaster, ReplaySpeed::AsFastAsPossible).await.unwrap();
+        feed.process().await.unwrap();
+
+        let ops = sub.recv().await.unwrap();
+        assert_eq!(ops.symbol(), Some("AAPL"));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
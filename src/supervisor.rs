@@ -0,0 +1,159 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use tokio::sync::{broadcast, mpsc};
+
+use crate::{
+    broadcast::FeedBroadcaster,
+    client::IQFeed,
+    command::{Command, IQFeedHandle},
+    config::Config,
+    errors::Error,
+};
+
+/// Which IQConnect service a supervised connection should reach.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Service {
+    Level1,
+    Lookup,
+    Admin,
+    Derivative,
+}
+
+/// Connection lifecycle states surfaced to consumers of a supervised feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Attempting to open the socket and complete the protocol handshake.
+    Connecting,
+    /// Connected and processing the feed.
+    Connected,
+    /// The connection was lost and is being retried with backoff.
+    Reconnecting,
+}
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Spawns a supervised connection to `service` and returns a handle for
+/// issuing subscription commands plus a receiver for connection-state
+/// transitions.
+///
+/// On connect failure or mid-session disconnect the supervisor retries
+/// with exponential backoff (capped at `MAX_BACKOFF`), re-sends the
+/// protocol handshake, and re-issues every currently-active watch command
+/// once the new connection comes up.
+///
+/// If `record_to` is `Some`, [`IQFeed::record_to`](crate::client::IQFeed::record_to)
+/// is called on every fresh connection before it starts processing, so a
+/// capture-to-file recording spans reconnects instead of ending with the
+/// connection that started it.
+pub(crate) fn spawn(
+    cfg: Config,
+    broadcaster: FeedBroadcaster,
+    service: Service,
+    record_to: Option<PathBuf>,
+) -> (IQFeedHandle, broadcast::Receiver<ConnectionState>) {
+    let (command_tx, mut command_rx) = mpsc::channel::<Command>(32);
+    let (state_tx, state_rx) = broadcast::channel(16);
+
+    tokio::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut active: HashMap<(&'static str, String), Command> = HashMap::new();
+
+        loop {
+            let _ = state_tx.send(ConnectionState::Connecting);
+
+            let (mut feed, inner_handle) = match connect(&cfg, service, broadcaster.clone()).await {
+                Ok(connected) => connected,
+                Err(_) => {
+                    let _ = state_tx.send(ConnectionState::Reconnecting);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            };
+
+            if let Some(path) = &record_to {
+                // Opening the recording file is just as fatal to this
+                // attempt as the connect itself failing: there's no feed
+                // running yet to lose, so retry the same way.
+                if feed.record_to(path).await.is_err() {
+                    let _ = state_tx.send(ConnectionState::Reconnecting);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            }
+            backoff = INITIAL_BACKOFF;
+
+            // `process` has to be running before we replay `active`: it's
+            // the only thing draining `inner_handle`'s channel, so with no
+            // receiver yet a watch list past the channel's capacity would
+            // block this resend forever instead of reaching the new
+            // connection.
+            let mut process = tokio::spawn(feed.process());
+
+            for command in active.values() {
+                if inner_handle.send(command.clone()).await.is_err() {
+                    continue;
+                }
+            }
+            let _ = state_tx.send(ConnectionState::Connected);
+
+            let disconnect = loop {
+                tokio::select! {
+                    result = &mut process => break result,
+                    Some(command) = command_rx.recv() => {
+                        if command.is_unwatch() {
+                            // IQConnect's `r` command cancels every kind of
+                            // watch on a symbol at once, so an unwatch of
+                            // any kind has to drop all of that symbol's
+                            // tracked entries, not just the one matching
+                            // its own kind.
+                            let (_, symbol) = command.active_key();
+                            active.retain(|(_, active_symbol), _| active_symbol != &symbol);
+                        } else {
+                            active.insert(command.active_key(), command.clone());
+                        }
+                        if inner_handle.send(command).await.is_err() {
+                            // The only way this send fails is if `process`
+                            // has already dropped its receiver by exiting,
+                            // so get its real outcome instead of making one
+                            // up: a fabricated success here would mask a
+                            // connection-fatal error as transient noise and
+                            // reset the backoff against a host that's
+                            // actually still down.
+                            break (&mut process).await;
+                        }
+                    }
+                }
+            };
+
+            let _ = state_tx.send(ConnectionState::Reconnecting);
+
+            // `process` only ever returns once the connection itself is
+            // gone, so reconnecting is required either way. But if it
+            // somehow exited over something that wasn't the socket dying
+            // (a bug resurrecting line-level parse errors, say), that's
+            // transient noise rather than a real outage: don't let it
+            // grow the backoff.
+            let fatal = !matches!(&disconnect, Ok(Err(err)) if !err.is_connection_fatal());
+            if fatal {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            } else {
+                backoff = INITIAL_BACKOFF;
+            }
+        }
+    });
+
+    (IQFeedHandle { commands: command_tx }, state_rx)
+}
+
+async fn connect(cfg: &Config, service: Service, broadcaster: FeedBroadcaster) -> Result<(IQFeed, IQFeedHandle), Error> {
+    match service {
+        Service::Level1 => IQFeed::connect_level1(cfg, broadcaster).await,
+        Service::Lookup => IQFeed::connect_lookup(cfg, broadcaster).await,
+        Service::Admin => IQFeed::connect_admin(cfg, broadcaster).await,
+        Service::Derivative => IQFeed::connect_derivative(cfg, broadcaster).await,
+    }
+}